@@ -3,45 +3,420 @@
     windows_subsystem = "windows"
 )]
 
-use tauri::{Manager, command};
-use tauri_plugin_decorum::WebviewWindowExt;
+mod search;
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
 use tauri::webview::Color;
+use tauri::{command, Emitter, Manager, State, WebviewUrl, WebviewWindow, WebviewWindowBuilder};
+use tauri_plugin_decorum::WebviewWindowExt;
+use tauri_plugin_window_state::{StateFlags, WindowExt};
+
+use search::{SearchConfig, SearchState};
+
+// How the app decides which appearance to use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum ThemeMode {
+    Light,
+    Dark,
+    System,
+}
+
+// Platform window-chrome styling.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+struct TitlebarStyle {
+    // macOS traffic-light inset from the window's top-left, if customised.
+    traffic_light_inset: Option<(f64, f64)>,
+    // On Windows/Linux, run frameless and draw our own window controls.
+    frameless: bool,
+}
+
+// User preferences persisted across launches under the app config dir.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Settings {
+    theme_mode: ThemeMode,
+    #[serde(default)]
+    titlebar: TitlebarStyle,
+    #[serde(default)]
+    search_config: SearchConfig,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            theme_mode: ThemeMode::System,
+            titlebar: TitlebarStyle::default(),
+            search_config: SearchConfig::default(),
+        }
+    }
+}
+
+// On-disk settings store, owning the resolved path to `settings.json`.
+struct SettingsStore {
+    path: PathBuf,
+}
+
+impl SettingsStore {
+    // Resolve the store location inside the app config dir.
+    fn new(app: &tauri::AppHandle) -> Result<Self, String> {
+        let dir = app.path().app_config_dir().map_err(|e| e.to_string())?;
+        Ok(Self { path: dir.join("settings.json") })
+    }
+
+    // Blocking read used at startup, before the async runtime serves IPC.
+    // Falls back to defaults on any missing/corrupt file.
+    fn read(path: &Path) -> Settings {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default()
+    }
+
+    // Blocking write, creating the config dir if it does not yet exist.
+    fn write(path: &Path, settings: &Settings) -> Result<(), String> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let raw = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+        std::fs::write(path, raw).map_err(|e| e.to_string())
+    }
+
+    // Synchronous load for the startup path only (see `read`).
+    fn load_blocking(&self) -> Settings {
+        Self::read(&self.path)
+    }
 
-// Command to handle search functionality
+    // Read settings off a blocking thread so command handlers never stall the
+    // async executor on filesystem I/O.
+    async fn load(&self) -> Settings {
+        let path = self.path.clone();
+        tauri::async_runtime::spawn_blocking(move || Self::read(&path))
+            .await
+            .unwrap_or_default()
+    }
+
+    // Write settings off a blocking thread, for the same reason as `load`.
+    async fn store(&self, settings: &Settings) -> Result<(), String> {
+        let path = self.path.clone();
+        let settings = settings.clone();
+        tauri::async_runtime::spawn_blocking(move || Self::write(&path, &settings))
+            .await
+            .map_err(|e| e.to_string())?
+    }
+
+    // Persist just the theme mode, preserving any other settings on disk.
+    async fn set_theme_mode(&self, mode: ThemeMode) -> Result<(), String> {
+        let mut settings = self.load().await;
+        settings.theme_mode = mode;
+        self.store(&settings).await
+    }
+
+    // Persist just the titlebar style, preserving any other settings on disk.
+    async fn set_titlebar(&self, titlebar: TitlebarStyle) -> Result<(), String> {
+        let mut settings = self.load().await;
+        settings.titlebar = titlebar;
+        self.store(&settings).await
+    }
+
+    // Persist just the search config, preserving any other settings on disk.
+    async fn set_search_config(&self, config: SearchConfig) -> Result<(), String> {
+        let mut settings = self.load().await;
+        settings.search_config = config;
+        self.store(&settings).await
+    }
+}
+
+// Central shared state, managed once and threaded into every command.
+struct AppState {
+    theme_mode: Mutex<ThemeMode>,
+    titlebar: Mutex<TitlebarStyle>,
+    search_config: Mutex<SearchConfig>,
+    search: SearchState,
+    settings: SettingsStore,
+}
+
+// Request to start a filesystem search.
+#[derive(Debug, Serialize, Deserialize)]
+struct SearchRequest {
+    query: String,
+    root: String,
+}
+
+// Acknowledgement that a background search was started; matches stream as events.
+#[derive(Debug, Serialize, Deserialize)]
+struct SearchResponse {
+    started: bool,
+}
+
+// Request to switch the theme mode.
+#[derive(Debug, Serialize, Deserialize)]
+struct ThemeRequest {
+    mode: ThemeMode,
+}
+
+// The mode now in effect after a theme command.
+#[derive(Debug, Serialize, Deserialize)]
+struct ThemeResponse {
+    mode: ThemeMode,
+}
+
+// Whether `open_settings` focused an existing window or created a new one.
+#[derive(Debug, Serialize, Deserialize)]
+struct OpenSettingsResponse {
+    already_open: bool,
+}
+
+// Map a resolved window/OS theme to the overlay titlebar background color.
+fn theme_color(theme: tauri::Theme) -> Color {
+    match theme {
+        tauri::Theme::Light => Color(255, 255, 255, 255),
+        _ => Color(32, 32, 32, 255),
+    }
+}
+
+// Default macOS traffic-light inset, applied when no custom position is set so
+// that clearing a previous inset reverts to the standard placement.
+#[cfg(target_os = "macos")]
+const DEFAULT_TRAFFIC_LIGHT_INSET: (f32, f32) = (20.0, 20.0);
+
+// Reconcile a window's chrome to the requested style: on macOS the
+// traffic-light inset is set to the custom value or reset to the default;
+// elsewhere the native frame is dropped only when `frameless` is requested.
+//
+// Note: frameless mode hides the native window frame, so the webview must draw
+// its own minimize/maximize/close controls. This does not by itself preserve
+// Windows 11 Snap Layouts, which rely on hit-testing the native maximize button.
+fn apply_titlebar(window: &WebviewWindow, style: &TitlebarStyle) {
+    #[cfg(target_os = "macos")]
+    {
+        let (x, y) = style
+            .traffic_light_inset
+            .map(|(x, y)| (x as f32, y as f32))
+            .unwrap_or(DEFAULT_TRAFFIC_LIGHT_INSET);
+        let _ = window.set_traffic_lights_inset(x, y);
+    }
+    #[cfg(not(target_os = "macos"))]
+    if style.frameless {
+        // Only drop the native frame when explicitly asked; otherwise leave
+        // whatever create_overlay_titlebar()/tauri.conf.json established intact.
+        let _ = window.set_decorations(false);
+    }
+}
+
+// Subscribe a window to live OS appearance changes: while the mode is System,
+// a ThemeChanged event recomputes the overlay background and lets the webview
+// restyle in lockstep. The guard keeps a live change from stomping an explicit
+// Light/Dark override.
+fn register_theme_listener(app: &tauri::AppHandle, window: &WebviewWindow) {
+    let handle = app.clone();
+    let event_window = window.clone();
+    window.on_window_event(move |event| {
+        if let tauri::WindowEvent::ThemeChanged(theme) = event {
+            let mode = *handle
+                .state::<AppState>()
+                .theme_mode
+                .lock()
+                .expect("theme mode lock poisoned");
+            if mode == ThemeMode::System {
+                let _ = event_window.set_background_color(Some(theme_color(*theme)));
+                let _ = event_window.emit("theme-changed", *theme);
+            }
+        }
+    });
+}
+
+// Apply a theme mode to a window and tell the webview, returning the resolved
+// theme. Light/Dark pin the window; System defers to the OS so later changes
+// arrive through the ThemeChanged handler. The emitted event carries the
+// resolved theme so a frameless, webview-drawn control region can repaint in
+// lockstep with the native overlay.
+fn apply_theme_mode(window: &WebviewWindow, mode: ThemeMode) -> tauri::Theme {
+    let resolved = match mode {
+        ThemeMode::Light => {
+            let _ = window.set_theme(Some(tauri::Theme::Light));
+            tauri::Theme::Light
+        }
+        ThemeMode::Dark => {
+            let _ = window.set_theme(Some(tauri::Theme::Dark));
+            tauri::Theme::Dark
+        }
+        ThemeMode::System => {
+            let _ = window.set_theme(None);
+            window.theme().unwrap_or(tauri::Theme::Light)
+        }
+    };
+    let _ = window.set_background_color(Some(theme_color(resolved)));
+    let _ = window.emit("theme-changed", resolved);
+    resolved
+}
+
+// Command to handle search functionality.
+//
+// Cancels any in-flight search, then kicks off a background walk of `root`
+// for `query`; ranked matches stream back to the frontend as `search-result`
+// events rather than being returned in bulk.
 #[command]
-async fn handle_search(query: &str, _window: tauri::Window) -> Result<String, String> {
-    println!("Search requested for: {}", query);
-    // In a real implementation, this would perform the actual search
-    Ok(format!("Searching for: {}", query))
+async fn handle_search(
+    request: SearchRequest,
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<SearchResponse, String> {
+    println!("Search requested for: {}", request.query);
+    let cancel = state.search.begin();
+    let config = state.search_config.lock().map_err(|e| e.to_string())?.clone();
+    search::spawn_search(app, PathBuf::from(request.root), request.query, config, cancel);
+    Ok(SearchResponse { started: true })
 }
 
-// Command to toggle theme
+// Command to toggle between the Light and Dark themes.
 #[command]
-async fn toggle_theme(window: tauri::Window) -> Result<String, String> {
+async fn toggle_theme(
+    window: tauri::Window,
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<ThemeResponse, String> {
     println!("Theme toggle requested");
-    // Toggle between light and dark theme
-    let current_theme = window.theme().unwrap_or(tauri::Theme::Light);
-    let new_theme = match current_theme {
+    let new_theme = match window.theme().unwrap_or(tauri::Theme::Light) {
         tauri::Theme::Light => tauri::Theme::Dark,
         tauri::Theme::Dark => tauri::Theme::Light,
         _ => tauri::Theme::Light, // Default to light theme for any other case
     };
-    window.set_theme(Some(new_theme)).unwrap();
-    // Adjust the overlay titlebar background color to match theme
-    let color = match new_theme {
-        tauri::Theme::Light => Color(255, 255, 255, 255),
-        _ => Color(32, 32, 32, 255),
+    // An explicit toggle is an explicit override, so remember it as such and
+    // stop following the OS until the user opts back into System.
+    let mode = match new_theme {
+        tauri::Theme::Dark => ThemeMode::Dark,
+        _ => ThemeMode::Light,
     };
-    let _ = window.set_background_color(Some(color));
-    Ok(format!("Theme toggled to {:?}", new_theme))
+    *state.theme_mode.lock().map_err(|e| e.to_string())? = mode;
+    let _ = state.settings.set_theme_mode(mode).await;
+    let view = app
+        .get_webview_window(window.label())
+        .ok_or_else(|| "window not found".to_string())?;
+    apply_theme_mode(&view, mode);
+    Ok(ThemeResponse { mode })
 }
 
-// Command to open settings
+// Command to set the theme mode (Light, Dark, or System).
 #[command]
-async fn open_settings(_window: tauri::Window) -> Result<String, String> {
+async fn set_theme_mode(
+    request: ThemeRequest,
+    window: tauri::Window,
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<ThemeResponse, String> {
+    println!("Theme mode requested: {:?}", request.mode);
+    *state.theme_mode.lock().map_err(|e| e.to_string())? = request.mode;
+    let _ = state.settings.set_theme_mode(request.mode).await;
+    let view = app
+        .get_webview_window(window.label())
+        .ok_or_else(|| "window not found".to_string())?;
+    apply_theme_mode(&view, request.mode);
+    Ok(ThemeResponse { mode: request.mode })
+}
+
+// Command to configure the window chrome (macOS traffic-light inset or
+// frameless custom controls), then repaint the control region for the
+// current theme.
+#[command]
+async fn configure_titlebar(
+    style: TitlebarStyle,
+    window: tauri::Window,
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    println!("Titlebar style requested: {:?}", style);
+    let view = app
+        .get_webview_window(window.label())
+        .ok_or_else(|| "window not found".to_string())?;
+    apply_titlebar(&view, &style);
+    // Repaint the controls with the current light/dark background color.
+    let mode = *state.theme_mode.lock().map_err(|e| e.to_string())?;
+    apply_theme_mode(&view, mode);
+    *state.titlebar.lock().map_err(|e| e.to_string())? = style;
+    let _ = state.settings.set_titlebar(style).await;
+    Ok(())
+}
+
+// Command to update the filesystem search configuration.
+#[command]
+async fn set_search_config(
+    config: SearchConfig,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    println!("Search config update requested");
+    *state.search_config.lock().map_err(|e| e.to_string())? = config.clone();
+    state.settings.set_search_config(config).await
+}
+
+// Command to read the persisted settings.
+#[command]
+async fn get_settings(state: State<'_, AppState>) -> Result<Settings, String> {
+    Ok(state.settings.load().await)
+}
+
+// Command to persist settings chosen from the frontend.
+#[command]
+async fn save_settings(
+    settings: Settings,
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    state.settings.store(&settings).await?;
+    *state.theme_mode.lock().map_err(|e| e.to_string())? = settings.theme_mode;
+    *state.titlebar.lock().map_err(|e| e.to_string())? = settings.titlebar;
+    *state.search_config.lock().map_err(|e| e.to_string())? = settings.search_config.clone();
+
+    // Keep the live window in sync with the stored preference.
+    if let Some(window) = app.get_webview_window("main") {
+        apply_titlebar(&window, &settings.titlebar);
+        apply_theme_mode(&window, settings.theme_mode);
+    }
+    Ok(())
+}
+
+// Command to open settings.
+//
+// Focuses the existing settings window if one is already open; otherwise
+// builds a second webview window that mirrors the main window's overlay
+// titlebar and theme-aware background. Closing it leaves the app running.
+#[command]
+async fn open_settings(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<OpenSettingsResponse, String> {
     println!("Settings requested");
-    // In a real implementation, this would open the settings window
-    Ok("Settings opened".to_string())
+    // Avoid spawning duplicates — just surface the one that already exists.
+    if let Some(window) = app.get_webview_window("settings") {
+        window.set_focus().map_err(|e| e.to_string())?;
+        return Ok(OpenSettingsResponse { already_open: true });
+    }
+
+    let settings_window = WebviewWindowBuilder::new(
+        &app,
+        "settings",
+        WebviewUrl::App("settings.html".into()),
+    )
+    .title("Settings")
+    .inner_size(480.0, 600.0)
+    .build()
+    .map_err(|e| e.to_string())?;
+
+    // Same decorum overlay titlebar, chrome style and persisted geometry as
+    // the main window.
+    let _ = settings_window.create_overlay_titlebar();
+    let _ = settings_window.restore_state(StateFlags::all());
+    let style = *state.titlebar.lock().map_err(|e| e.to_string())?;
+    apply_titlebar(&settings_window, &style);
+
+    // Theme-aware background matching the current mode, and the same live
+    // OS-appearance subscription the main window has.
+    let mode = *state.theme_mode.lock().map_err(|e| e.to_string())?;
+    apply_theme_mode(&settings_window, mode);
+    register_theme_listener(&app, &settings_window);
+    Ok(OpenSettingsResponse { already_open: false })
 }
 
 fn main() {
@@ -50,18 +425,40 @@ fn main() {
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_window_state::Builder::default().build())
         .plugin(tauri_plugin_decorum::init())
-        .invoke_handler(tauri::generate_handler![handle_search, toggle_theme, open_settings])
+        .invoke_handler(tauri::generate_handler![
+            handle_search,
+            toggle_theme,
+            set_theme_mode,
+            configure_titlebar,
+            set_search_config,
+            get_settings,
+            save_settings,
+            open_settings
+        ])
         .setup(|app| {
-            // Create overlay titlebar for main window with native buttons
+            // Build shared state, seeding the theme mode and titlebar style from disk.
+            let settings = SettingsStore::new(&app.handle()).expect("resolve settings path");
+            let saved = settings.load_blocking();
+            let saved_mode = saved.theme_mode;
+            let saved_titlebar = saved.titlebar;
+            app.manage(AppState {
+                theme_mode: Mutex::new(saved_mode),
+                titlebar: Mutex::new(saved_titlebar),
+                search_config: Mutex::new(saved.search_config),
+                search: SearchState::default(),
+                settings,
+            });
+
+            // Create overlay titlebar for main window with native buttons, apply
+            // the restored chrome style, then paint it for the saved theme.
             let main_window = app.get_webview_window("main").unwrap();
             let _ = main_window.create_overlay_titlebar();
-            // Theme-aware background on startup (apply to window)
-            let color = match main_window.theme().unwrap_or(tauri::Theme::Light) {
-                tauri::Theme::Light => Color(255, 255, 255, 255),
-                _ => Color(32, 32, 32, 255),
-            };
-            let _ = main_window.set_background_color(Some(color));
-            
+            apply_titlebar(&main_window, &saved_titlebar);
+            apply_theme_mode(&main_window, saved_mode);
+
+            // Follow live OS appearance changes while in System mode.
+            register_theme_listener(&app.handle(), &main_window);
+
             #[cfg(debug_assertions)]
             {
                 let window = app.get_webview_window("main").unwrap();
@@ -71,4 +468,4 @@ fn main() {
         })
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
-}
\ No newline at end of file
+}
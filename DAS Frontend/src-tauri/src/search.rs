@@ -0,0 +1,187 @@
+//! Filesystem full-text search backend.
+//!
+//! A search walks a root directory on a background task, reads text-like
+//! files, and streams ranked matches to the frontend as `search-result`
+//! events. Each new query cancels the previous in-flight walk.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+/// Tuning knobs for a filesystem search.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchConfig {
+    /// Glob patterns whose matching paths are skipped entirely.
+    pub ignored_globs: Vec<String>,
+    /// Files larger than this many bytes are assumed binary/huge and skipped.
+    pub max_file_size: u64,
+}
+
+impl Default for SearchConfig {
+    fn default() -> Self {
+        Self {
+            ignored_globs: vec![
+                "*/.git/*".into(),
+                "*/node_modules/*".into(),
+                "*/target/*".into(),
+                "*.lock".into(),
+            ],
+            max_file_size: 2 * 1024 * 1024,
+        }
+    }
+}
+
+/// A single ranked hit, emitted to the frontend as soon as it is found.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchMatch {
+    pub path: String,
+    pub line: usize,
+    pub snippet: String,
+    /// Number of query occurrences on the line — higher ranks more relevant.
+    pub score: usize,
+}
+
+/// Holds the cancellation flag for the in-flight search, if any.
+#[derive(Default)]
+pub struct SearchState {
+    cancel: Mutex<Option<Arc<AtomicBool>>>,
+}
+
+impl SearchState {
+    /// Cancel any running search and hand back a fresh token for the new one.
+    pub fn begin(&self) -> Arc<AtomicBool> {
+        let token = Arc::new(AtomicBool::new(false));
+        let mut guard = self.cancel.lock().expect("search cancel lock poisoned");
+        if let Some(previous) = guard.replace(token.clone()) {
+            previous.store(true, Ordering::SeqCst);
+        }
+        token
+    }
+}
+
+/// Spawn a background walk that streams matches until it finishes or is cancelled.
+///
+/// The walk performs blocking filesystem I/O, so it runs on a blocking thread
+/// rather than the async executor to keep other IPC commands responsive.
+pub fn spawn_search(
+    app: AppHandle,
+    root: PathBuf,
+    query: String,
+    config: SearchConfig,
+    cancel: Arc<AtomicBool>,
+) {
+    tauri::async_runtime::spawn_blocking(move || {
+        let needle = query.to_lowercase();
+        if needle.is_empty() {
+            return;
+        }
+        let mut stack = vec![root];
+        while let Some(dir) = stack.pop() {
+            if cancel.load(Ordering::SeqCst) {
+                return;
+            }
+            let entries = match std::fs::read_dir(&dir) {
+                Ok(entries) => entries,
+                Err(_) => continue,
+            };
+            for entry in entries.flatten() {
+                if cancel.load(Ordering::SeqCst) {
+                    return;
+                }
+                let path = entry.path();
+                if is_ignored(&path, &config.ignored_globs) {
+                    continue;
+                }
+                let metadata = match entry.metadata() {
+                    Ok(metadata) => metadata,
+                    Err(_) => continue,
+                };
+                if metadata.is_dir() {
+                    stack.push(path);
+                } else if metadata.len() <= config.max_file_size {
+                    search_file(&app, &path, &needle, &cancel);
+                }
+            }
+        }
+    });
+}
+
+/// Scan one file line by line, emitting a match event per hit.
+fn search_file(app: &AppHandle, path: &Path, needle: &str, cancel: &AtomicBool) {
+    let bytes = match std::fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(_) => return,
+    };
+    // A NUL byte is a cheap, reliable signal that the file is not text.
+    if bytes.contains(&0) {
+        return;
+    }
+    let text = String::from_utf8_lossy(&bytes);
+    let display = path.display().to_string();
+    for (index, line) in text.lines().enumerate() {
+        if cancel.load(Ordering::SeqCst) {
+            return;
+        }
+        let score = line.to_lowercase().matches(needle).count();
+        if score > 0 {
+            let hit = SearchMatch {
+                path: display.clone(),
+                line: index + 1,
+                snippet: snippet(line),
+                score,
+            };
+            let _ = app.emit("search-result", hit);
+        }
+    }
+}
+
+/// Trim surrounding whitespace and cap a matched line to a readable length.
+fn snippet(line: &str) -> String {
+    const MAX: usize = 200;
+    let trimmed = line.trim();
+    match trimmed.char_indices().nth(MAX) {
+        Some((byte, _)) => format!("{}…", &trimmed[..byte]),
+        None => trimmed.to_string(),
+    }
+}
+
+/// Whether any ignored glob matches the (slash-normalised) path.
+fn is_ignored(path: &Path, globs: &[String]) -> bool {
+    let normalised = path.to_string_lossy().replace('\\', "/");
+    // Also test a trailing-slash form so a directory matches container globs
+    // like `*/node_modules/*` and is pruned before we descend into it.
+    let as_dir = format!("{normalised}/");
+    globs
+        .iter()
+        .any(|glob| glob_match(glob, &normalised) || glob_match(glob, &as_dir))
+}
+
+/// Minimal `*`-wildcard matcher (`*` spans any run of characters, `/` included).
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let segments: Vec<&str> = pattern.split('*').collect();
+    if segments.len() == 1 {
+        return pattern == text;
+    }
+    let mut pos = 0usize;
+    for (index, segment) in segments.iter().enumerate() {
+        if segment.is_empty() {
+            continue;
+        }
+        if index == 0 {
+            if !text[pos..].starts_with(segment) {
+                return false;
+            }
+            pos += segment.len();
+        } else if index == segments.len() - 1 {
+            return text[pos..].ends_with(segment);
+        } else if let Some(found) = text[pos..].find(segment) {
+            pos += found + segment.len();
+        } else {
+            return false;
+        }
+    }
+    true
+}